@@ -5,6 +5,7 @@ use std::sync::{
     Mutex,
 };
 use std::collections::HashMap;
+use std::collections::VecDeque;
 use std::fmt;
 use std::io;
 use std::io::{
@@ -51,12 +52,18 @@ enum Command {
     EndGame,
 }
 
-#[derive(Debug, Eq, PartialEq)]
-enum Action {
+/// An action that can be taken by a cell.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Action {
+    /// Move the cell at `cell_id` into `(x, y)`.
     Move{ cell_id: u32, x: u32, y: u32 },
+    /// Make the cell at `cell_id` attack `(x, y)`.
     Attack{ cell_id: u32, x: u32, y: u32 },
+    /// Make the cell at `cell_id` explode.
     Explode{ cell_id: u32 },
+    #[doc(hidden)]
     Initialized,
+    #[doc(hidden)]
     RoundEnd,
 }
 
@@ -131,7 +138,7 @@ impl FromStr for Command {
 struct CommunicatorDetails {
     input: io::BufReader<Stdin>,
     output: io::BufWriter<Stdout>,
-    pending_actions: Vec<Action>,
+    pending_actions: HashMap<u32, Action>,
 }
 
 #[doc(hidden)]
@@ -145,13 +152,31 @@ impl Communicator {
             details: Mutex::new(CommunicatorDetails{
                 input: io::BufReader::new(input),
                 output: io::BufWriter::new(output),
-                pending_actions: Vec::new(),
+                pending_actions: HashMap::new(),
             })
         }
     }
 
+    fn action_cell_id(action: &Action) -> u32 {
+        match action {
+            Action::Move{ cell_id, .. } => *cell_id,
+            Action::Attack{ cell_id, .. } => *cell_id,
+            Action::Explode{ cell_id } => *cell_id,
+            Action::Initialized | Action::RoundEnd => panic!("actions without a cell id can't be queued"),
+        }
+    }
+
     fn add_action(&self, action: Action) {
-        self.details.lock().unwrap().pending_actions.push(action);
+        let cell_id = Self::action_cell_id(&action);
+        self.details.lock().unwrap().pending_actions.insert(cell_id, action);
+    }
+
+    fn pending_action(&self, cell_id: u32) -> Option<Action> {
+        self.details.lock().unwrap().pending_actions.get(&cell_id).cloned()
+    }
+
+    fn clear_action(&self, cell_id: u32) {
+        self.details.lock().unwrap().pending_actions.remove(&cell_id);
     }
 
     fn send_action(
@@ -177,11 +202,11 @@ impl Communicator {
 
     fn end_round(&self) -> io::Result<()> {
         let mut details = self.details.lock().unwrap();
-        let mut pending_actions = take(&mut details.pending_actions);
-        pending_actions.push(Action::RoundEnd);
-        for action in pending_actions.into_iter() {
+        let pending_actions = take(&mut details.pending_actions);
+        for action in pending_actions.into_values() {
             Self::send_action(action, &mut details)?;
         }
+        Self::send_action(Action::RoundEnd, &mut details)?;
         Self::flush(&mut details)?;
         Ok(())
     }
@@ -195,7 +220,7 @@ impl Communicator {
 }
 
 /// A position, defined by a tuple of x and y coordinates.
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Position {
     x: i32,
     y: i32,
@@ -257,6 +282,7 @@ impl Position {
 }
 
 /// A direction.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum Direction {
     /// North (up)
     North,
@@ -266,20 +292,47 @@ pub enum Direction {
     East,
     /// West (left)
     West,
+    /// North-east (up and to the right)
+    NorthEast,
+    /// South-east (down and to the right)
+    SouthEast,
+    /// South-west (down and to the left)
+    SouthWest,
+    /// North-west (up and to the left)
+    NorthWest,
 }
 
 impl Direction {
+    /// Gets all of the possible directions, including diagonals.
+    pub fn all() -> [Direction; 8] {
+        [
+            Direction::North,
+            Direction::South,
+            Direction::East,
+            Direction::West,
+            Direction::NorthEast,
+            Direction::SouthEast,
+            Direction::SouthWest,
+            Direction::NorthWest,
+        ]
+    }
+
     fn as_position_offset(&self) -> (i32, i32) {
         match self {
             Direction::North => (0, -1),
             Direction::South => (0, 1),
             Direction::East => (1, 0),
             Direction::West => (-1, 0),
+            Direction::NorthEast => (1, -1),
+            Direction::SouthEast => (1, 1),
+            Direction::SouthWest => (-1, 1),
+            Direction::NorthWest => (-1, -1),
         }
     }
 }
 
 /// A cell in the game
+#[derive(Clone)]
 pub struct Cell {
     cell_id: u32,
     position: Position,
@@ -288,7 +341,7 @@ pub struct Cell {
     age: u32,
     is_enemy: bool,
     communicator: Arc<Communicator>,
-    world_properties: WorldProperties,
+    world_properties: Arc<WorldProperties>,
 }
 
 impl Cell {
@@ -372,6 +425,15 @@ impl Cell {
         self.can_attack_position(&cell.position)
     }
 
+    /// Indicates if the cell can attack in the given direction.
+    ///
+    /// # Arguments
+    ///
+    /// * `direction` - the direction.
+    pub fn can_attack_in_direction(&self, direction: &Direction) -> bool {
+        self.can_attack_position(&self.position.translated_by_direction(direction))
+    }
+
     /// Instructs this cell to attack the given cell.
     ///
     /// See the documentation on the restrictions on attacking too-far-away positions.
@@ -400,6 +462,19 @@ impl Cell {
         }
     }
 
+    /// Instructs this cell to attack in the given direction.
+    ///
+    /// See the documentation on the restrictions on attacking too-far-away positions.
+    ///
+    /// # Arguments
+    ///
+    /// * `direction` - the direction to attack in.
+    pub fn attack_in_direction(&self, direction: &Direction) {
+        if self.can_attack_in_direction(direction) {
+            self.attack_position(&self.position.translated_by_direction(direction));
+        }
+    }
+
     /// Instructs this cell to move into the given position.
     ///
     /// See the documentation on the restrictions on moving into too-far-away positions and movement conflicts.
@@ -436,6 +511,19 @@ impl Cell {
     pub fn explode(&self) {
         self.communicator.add_action(Action::Explode{cell_id: self.cell_id});
     }
+
+    /// Gets the action currently queued for this cell, if any.
+    ///
+    /// Only one action can be queued per cell: queuing a new one (via e.g. [`Cell::move_to_position`],
+    /// [`Cell::attack_position`] or [`Cell::explode`]) replaces whatever was queued before.
+    pub fn pending_action(&self) -> Option<Action> {
+        self.communicator.pending_action(self.cell_id)
+    }
+
+    /// Retracts whatever action is currently queued for this cell, if any.
+    pub fn clear_action(&self) {
+        self.communicator.clear_action(self.cell_id);
+    }
 }
 
 impl fmt::Debug for Cell {
@@ -461,10 +549,14 @@ struct WorldProperties {
 }
 
 /// The state of the game's world.
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct WorldState {
     cells: HashMap<u32, Cell>,
-    properties: WorldProperties,
+    properties: Arc<WorldProperties>,
+    position_index: HashMap<(i32, i32), u32>,
+    conflicting_positions: Vec<Position>,
+    my_cell_ids: Vec<u32>,
+    enemy_cell_ids: Vec<u32>,
 }
 
 impl WorldState {
@@ -493,17 +585,341 @@ impl WorldState {
         self.properties.enemy_column
     }
 
+    /// Gets the positions where one of your moves conflicted with another one of your cells'
+    /// last round, causing neither of them to move. This is reset at the start of every round.
+    pub fn conflicting_positions(&self) -> &[Position] {
+        &self.conflicting_positions
+    }
+
     /// Gets all of the cells you currently control
+    ///
+    /// This iterates over a list of cell identifiers that's maintained incrementally as cells
+    /// spawn and die, rather than scanning every cell in the world. See also
+    /// [`WorldState::my_cells_iter`], which borrows directly out of this `WorldState` instead of
+    /// allocating a `Vec`.
     pub fn my_cells(&self) -> Vec<&Cell> {
-        self.cells.values().filter(|cell| cell.team_id == self.my_team_id()).collect()
+        self.my_cells_iter().collect()
     }
 
     /// Gets all of the cells the enemy currently controls
+    ///
+    /// This iterates over a list of cell identifiers that's maintained incrementally as cells
+    /// spawn and die, rather than scanning every cell in the world. See also
+    /// [`WorldState::enemy_cells_iter`], which borrows directly out of this `WorldState` instead
+    /// of allocating a `Vec`.
     pub fn enemy_cells(&self) -> Vec<&Cell> {
-        self.cells.values().filter(|cell| cell.team_id != self.my_team_id()).collect()
+        self.enemy_cells_iter().collect()
+    }
+
+    /// Gets all of the cells you currently control, without allocating a `Vec`.
+    ///
+    /// This is the allocation-free counterpart to [`WorldState::my_cells`], useful for bots that
+    /// just want to iterate the result once per round.
+    pub fn my_cells_iter(&self) -> impl Iterator<Item = &Cell> {
+        self.my_cell_ids.iter().filter_map(|cell_id| self.cells.get(cell_id))
+    }
+
+    /// Gets all of the cells the enemy currently controls, without allocating a `Vec`.
+    ///
+    /// This is the allocation-free counterpart to [`WorldState::enemy_cells`], useful for bots
+    /// that just want to iterate the result once per round.
+    pub fn enemy_cells_iter(&self) -> impl Iterator<Item = &Cell> {
+        self.enemy_cell_ids.iter().filter_map(|cell_id| self.cells.get(cell_id))
+    }
+
+    fn cell_id_at(&self, position: &Position) -> Option<u32> {
+        self.position_index.get(&(position.x(), position.y())).copied()
+    }
+
+    /// Rebuilds `position_index` from scratch based on the current contents of `cells`.
+    ///
+    /// Patching the index in place as individual cells move would make it depend on the order
+    /// commands are applied in: if a cell moves into the square another cell is vacating in the
+    /// same batch, whichever update lands first gets overwritten by the other's stale `remove`.
+    /// Rebuilding once after a batch of updates sidesteps that entirely.
+    fn rebuild_position_index(&mut self) {
+        self.position_index.clear();
+        self.position_index.extend(self.cells.values().map(|cell| ((cell.position.x(), cell.position.y()), cell.cell_id)));
+    }
+
+    /// Gets the cell at the given position, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - the position to look up.
+    pub fn cell_at(&self, position: &Position) -> Option<&Cell> {
+        self.cell_id_at(position).and_then(|cell_id| self.cells.get(&cell_id))
+    }
+
+    /// Gets all of the cells within `range` squares of `position`, using the Manhattan distance.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - the position to search around.
+    /// * `range` - the maximum Manhattan distance a cell can be at to be included.
+    pub fn cells_within_range(&self, position: &Position, range: u64) -> Vec<&Cell> {
+        let range = range as i32;
+        let mut cells = Vec::new();
+        for dx in -range..=range {
+            let remaining = range - dx.abs();
+            for dy in -remaining..=remaining {
+                if let Some(cell) = self.cell_at(&position.translated_by_offset(dx, dy)) {
+                    cells.push(cell);
+                }
+            }
+        }
+        cells
+    }
+
+    /// Gets the 8 cells surrounding the given cell, if present.
+    ///
+    /// # Arguments
+    ///
+    /// * `cell` - the cell whose surroundings are to be looked up.
+    pub fn neighbors(&self, cell: &Cell) -> Vec<&Cell> {
+        Direction::all()
+            .iter()
+            .filter_map(|direction| self.cell_at(&cell.position.translated_by_direction(direction)))
+            .collect()
+    }
+
+    /// Gets the closest enemy cell to the given cell, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `cell` - the cell to search around.
+    pub fn nearest_enemy(&self, cell: &Cell) -> Option<&Cell> {
+        self.cells
+            .values()
+            .filter(|candidate| candidate.team_id != cell.team_id)
+            .min_by_key(|candidate| candidate.position.distance(&cell.position))
+    }
+
+    fn is_in_bounds(&self, position: &Position) -> bool {
+        position.x() >= 0 &&
+            position.y() >= 0 &&
+            position.x() < self.width() as i32 &&
+            position.y() < self.height() as i32
+    }
+
+    fn grid_index(&self, position: &Position) -> usize {
+        position.y() as usize * self.width() as usize + position.x() as usize
+    }
+
+    /// Finds the shortest path between two positions and returns it as the sequence of
+    /// orthogonal directions to follow, or `None` if `to` is unreachable from `from`.
+    ///
+    /// The search treats in-bounds empty squares as walkable and squares occupied by a cell as
+    /// blocked, except for `to` itself, which is always considered reachable. Since boards can be
+    /// large, the search gives up and returns `None` after exploring [`PATHFINDING_NODE_BUDGET`]
+    /// squares.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - the starting position.
+    /// * `to` - the target position.
+    pub fn path_to(&self, from: &Position, to: &Position) -> Option<Vec<Direction>> {
+        const DIRECTIONS: [Direction; 4] = [Direction::North, Direction::South, Direction::East, Direction::West];
+
+        if !self.is_in_bounds(from) || !self.is_in_bounds(to) {
+            return None;
+        }
+        let mut visited = vec![false; self.width() as usize * self.height() as usize];
+        let mut came_from: HashMap<(i32, i32), ((i32, i32), Direction)> = HashMap::new();
+        let mut queue = VecDeque::new();
+        visited[self.grid_index(from)] = true;
+        queue.push_back(from.clone());
+        let mut explored = 0;
+
+        while let Some(current) = queue.pop_front() {
+            if current == *to {
+                let mut path = Vec::new();
+                let mut key = (current.x(), current.y());
+                while let Some((previous, direction)) = came_from.get(&key) {
+                    path.push(*direction);
+                    key = *previous;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            explored += 1;
+            if explored > PATHFINDING_NODE_BUDGET {
+                return None;
+            }
+            for direction in DIRECTIONS {
+                let next = current.translated_by_direction(&direction);
+                if !self.is_in_bounds(&next) {
+                    continue;
+                }
+                let index = self.grid_index(&next);
+                if visited[index] {
+                    continue;
+                }
+                if next != *to && self.cell_at(&next).is_some() {
+                    continue;
+                }
+                visited[index] = true;
+                came_from.insert((next.x(), next.y()), ((current.x(), current.y()), direction));
+                queue.push_back(next);
+            }
+        }
+        None
+    }
+
+    /// Gets the first step of the shortest path from `from` to `to`.
+    ///
+    /// This is a convenience wrapper around [`WorldState::path_to`] for bots that just want to
+    /// know which way to move next.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - the starting position.
+    /// * `to` - the target position.
+    pub fn step_toward(&self, from: &Position, to: &Position) -> Option<Direction> {
+        self.path_to(from, to).and_then(|path| path.into_iter().next())
+    }
+}
+
+/// The maximum number of squares [`WorldState::path_to`] will explore before giving up on
+/// finding a path. This is a fixed limit: neither [`WorldState::path_to`] nor
+/// [`WorldState::step_toward`] takes a budget of its own, so a target that needs more squares
+/// explored than this is reported as unreachable.
+pub const PATHFINDING_NODE_BUDGET: usize = 4096;
+
+/// The configuration used to run a [`simulate`] call.
+///
+/// The damage values depend on the rules of the server this bot is being run against, so they're
+/// not hardcoded and must be provided by the caller.
+pub struct SimulationConfig {
+    /// The amount of health taken off a cell hit by an `Attack` action.
+    pub attack_damage: u32,
+    /// The amount of health taken off every cell surrounding an `Explode` action.
+    pub explosion_damage: u32,
+}
+
+impl SimulationConfig {
+    /// Creates a new simulation configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `attack_damage` - the damage dealt by an `Attack` action.
+    /// * `explosion_damage` - the damage dealt by an `Explode` action to every surrounding cell.
+    pub fn new(attack_damage: u32, explosion_damage: u32) -> Self {
+        Self {
+            attack_damage,
+            explosion_damage,
+        }
     }
 }
 
+/// The outcome of a simulated round.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum SimulationOutcome {
+    /// The team with the given identifier is the only one with cells left.
+    PlayerWon(u32),
+    /// No team has any cells left.
+    Draw,
+    /// Both teams still have cells left.
+    Continue,
+}
+
+fn apply_damage(world_state: &mut WorldState, cell_id: u32, damage: u32) {
+    if let Some(cell) = world_state.cells.get_mut(&cell_id) {
+        cell.health = cell.health.saturating_sub(damage);
+        if cell.health == 0 {
+            let position = cell.position.clone();
+            world_state.cells.remove(&cell_id);
+            world_state.position_index.remove(&(position.x(), position.y()));
+        }
+    }
+}
+
+/// Simulates the effect of applying the given actions on top of `world_state`.
+///
+/// This allows a bot to evaluate "what happens if I play these moves" before actually committing
+/// to them, which enables lookahead strategies such as minimax. `world_state` is left untouched;
+/// the resulting state is returned alongside the [`SimulationOutcome`] it leads to.
+///
+/// Actions are resolved in the same order the server uses: all moves are applied first (any
+/// destination targeted by more than one cell is a conflict and none of those cells move), then
+/// attacks, then explosions.
+///
+/// # Arguments
+///
+/// * `world_state` - the state to simulate on top of.
+/// * `actions` - the actions to apply.
+/// * `config` - the damage values to use, since these depend on the server's ruleset.
+pub fn simulate(
+    world_state: &WorldState,
+    actions: &[Action],
+    config: &SimulationConfig,
+) -> (WorldState, SimulationOutcome)
+{
+    let mut world_state = world_state.clone();
+
+    let mut destinations: HashMap<(i32, i32), Vec<u32>> = HashMap::new();
+    for action in actions {
+        if let Action::Move{ cell_id, x, y } = action {
+            destinations.entry((*x as i32, *y as i32)).or_default().push(*cell_id);
+        }
+    }
+    for ((x, y), cell_ids) in &destinations {
+        if cell_ids.len() != 1 {
+            continue;
+        }
+        let cell_id = cell_ids[0];
+        let destination = Position{x: *x, y: *y};
+        if let Some(cell) = world_state.cells.get(&cell_id) {
+            if cell.is_in_bounds(&destination) && cell.position.distance(&destination) == 1 {
+                world_state.cells.get_mut(&cell_id).unwrap().position = destination;
+            }
+        }
+    }
+    // `destinations` is a HashMap, so the moves above apply in an unspecified order; a cell
+    // moving into a square another cell is vacating in this same batch would otherwise clobber
+    // the index depending on which one ran first. Rebuilding from scratch sidesteps that.
+    world_state.rebuild_position_index();
+
+    for action in actions {
+        if let Action::Attack{ x, y, .. } = action {
+            let target = Position{x: *x as i32, y: *y as i32};
+            if let Some(target_id) = world_state.cell_id_at(&target) {
+                apply_damage(&mut world_state, target_id, config.attack_damage);
+            }
+        }
+    }
+
+    for action in actions {
+        if let Action::Explode{ cell_id } = action {
+            let center = match world_state.cells.get(cell_id) {
+                Some(cell) => cell.position.clone(),
+                None => continue,
+            };
+            world_state.cells.remove(cell_id);
+            world_state.position_index.remove(&(center.x(), center.y()));
+            for direction in Direction::all() {
+                let neighbor = center.translated_by_direction(&direction);
+                if let Some(neighbor_id) = world_state.cell_id_at(&neighbor) {
+                    apply_damage(&mut world_state, neighbor_id, config.explosion_damage);
+                }
+            }
+        }
+    }
+
+    let mut teams = world_state.cells.values().map(|cell| cell.team_id);
+    let outcome = match teams.next() {
+        None => SimulationOutcome::Draw,
+        Some(first_team) => {
+            if teams.all(|team_id| team_id == first_team) {
+                SimulationOutcome::PlayerWon(first_team)
+            } else {
+                SimulationOutcome::Continue
+            }
+        },
+    };
+    (world_state, outcome)
+}
+
 #[doc(hidden)]
 pub struct GameCoordinator {
     communicator: Arc<Communicator>,
@@ -517,40 +933,49 @@ impl GameCoordinator {
     }
 
     fn apply_initialize(
+        world_state: &mut WorldState,
         width: u32,
         height: u32,
         my_team_id: u32,
         my_column: u32,
         enemy_column: u32,
-    ) -> WorldState
+    )
     {
-        WorldState {
-            properties: WorldProperties{
+        *world_state = WorldState {
+            properties: Arc::new(WorldProperties{
                 width,
                 height,
                 my_team_id,
                 my_column,
                 enemy_column,
-            },
+            }),
             ..Default::default()
-        }
+        };
     }
 
     fn apply_spawn(
         &self,
-        mut world_state: WorldState,
+        world_state: &mut WorldState,
         cell_id: u32,
         x: u32,
         y: u32,
         health: u32,
         team_id: u32,
         age: u32,
-    ) -> WorldState
+    )
     {
         let is_enemy = team_id != world_state.my_team_id();
+        let position = Position{x: x as i32, y: y as i32};
+        if !world_state.cells.contains_key(&cell_id) {
+            if is_enemy {
+                world_state.enemy_cell_ids.push(cell_id);
+            } else {
+                world_state.my_cell_ids.push(cell_id);
+            }
+        }
         world_state.cells.insert(cell_id, Cell{
             cell_id,
-            position: Position{x: x as i32, y: y as i32},
+            position,
             health,
             team_id,
             age,
@@ -558,28 +983,35 @@ impl GameCoordinator {
             communicator: self.communicator.clone(),
             world_properties: world_state.properties.clone(),
         });
-        world_state
     }
 
     fn apply_set_cell_properties(
-        mut world_state: WorldState,
+        world_state: &mut WorldState,
         cell_id: u32,
         x: u32,
         y: u32,
         health: u32,
         age: u32,
-    ) -> WorldState
+    )
     {
+        let position = Position{x: x as i32, y: y as i32};
         let cell = world_state.cells.get_mut(&cell_id).expect("Invalid cell id");
-        cell.position = Position{x: x as i32, y: y as i32};
+        cell.position = position;
         cell.health = health;
         cell.age = age;
-        world_state
     }
 
-    fn apply_die(mut world_state: WorldState, cell_id: u32) -> WorldState {
-        world_state.cells.remove(&cell_id);
-        world_state
+    fn apply_die(world_state: &mut WorldState, cell_id: u32) {
+        if let Some(cell) = world_state.cells.remove(&cell_id) {
+            let ids = if cell.is_enemy { &mut world_state.enemy_cell_ids } else { &mut world_state.my_cell_ids };
+            if let Some(index) = ids.iter().position(|id| *id == cell_id) {
+                ids.swap_remove(index);
+            }
+        }
+    }
+
+    fn apply_conflicting_actions(world_state: &mut WorldState, x: u32, y: u32) {
+        world_state.conflicting_positions.push(Position{x: x as i32, y: y as i32});
     }
 
     fn advertise_initialization(&self) -> io::Result<()> {
@@ -590,21 +1022,22 @@ impl GameCoordinator {
     fn apply_command(
         &self,
         command: Command,
-        world_state: WorldState,
-    ) -> WorldState
+        world_state: &mut WorldState,
+    )
     {
-        let world_state = match command {
+        match command {
             Command::Initialize{ width, height, team_id, my_column, enemy_column } =>
-                Self::apply_initialize(width, height, team_id, my_column, enemy_column),
+                Self::apply_initialize(world_state, width, height, team_id, my_column, enemy_column),
             Command::Spawn{ cell_id, x, y, health, team_id, age} =>
                 self.apply_spawn(world_state, cell_id, x, y, health, team_id, age),
             Command::Die{ cell_id } =>
                 Self::apply_die(world_state, cell_id),
             Command::SetCellProperties{ cell_id, x, y, health, age } =>
                 Self::apply_set_cell_properties(world_state, cell_id, x, y, health, age),
-            _ => world_state,
-        };
-        world_state
+            Command::ConflictingActions{ x, y } =>
+                Self::apply_conflicting_actions(world_state, x, y),
+            _ => {},
+        }
     }
 
     pub fn run_loop<B>(&self, mut bot: B) -> Result<(), Box<dyn Error>>
@@ -618,10 +1051,12 @@ impl GameCoordinator {
             if command == Command::EndGame {
                 break;
             }
+            world_state.conflicting_positions.clear();
             while command != Command::RunRound {
-                world_state = self.apply_command(command, world_state);
+                self.apply_command(command, &mut world_state);
                 command = self.communicator.read_command()?;
             }
+            world_state.rebuild_position_index();
             bot.run_round(&world_state);
             self.communicator.end_round()?;
         }
@@ -635,8 +1070,9 @@ impl GameCoordinator {
 pub trait UserBot {
     /// Run a particular round of the game.
     ///
-    /// The implementation of this method must guarantee that at most one action is emitted per
-    /// cell.
+    /// At most one action is sent per cell: queuing a second action (e.g. calling
+    /// [`Cell::move_to_position`] after [`Cell::attack_position`]) on the same cell replaces the
+    /// one that was queued before it.
     ///
     /// # Arguments
     ///